@@ -185,7 +185,7 @@ export function MultiplayerQuoridorGame({ gameId }: MultiplayerQuoridorGameProps
     if (wallsRemaining[myPlayerSide] <= 0) return;
     if (!canPlaceWall(wall, walls, positions)) return;
 
-    const nextWalls = [...walls, wall];
+    const nextWalls = [...walls, { ...wall, owner: myPlayerSide }];
     const nextWallsRemaining = {
       ...wallsRemaining,
       [myPlayerSide]: wallsRemaining[myPlayerSide] - 1,
@@ -351,10 +351,17 @@ export function MultiplayerQuoridorGame({ gameId }: MultiplayerQuoridorGameProps
     const thinkingDelay = aiInstance.getThinkingTime();
     const timer = setTimeout(async () => {
       const aiWallsLeft = wallsRemaining[aiPlayerSide] ?? 0;
-      const move = aiInstance.getBestMoveForSide(aiPlayerSide, positions, walls, aiWallsLeft);
+      const opponentWallsLeft = wallsRemaining[getOpponent(aiPlayerSide)] ?? 0;
+      const move = aiInstance.getBestMoveForSide(aiPlayerSide, positions, walls, aiWallsLeft, opponentWallsLeft);
       aiMoveInProgressRef.current = false;
 
+      // Null means the stored state is invalid or leaves no legal move; the same state is not retried
       if (!move) {
+        showAlert({
+          title: 'Computer Stuck',
+          message: 'The computer cannot move from this position.',
+          buttons: [{ text: 'OK', style: 'default' }],
+        });
         return;
       }
 
@@ -375,7 +382,7 @@ export function MultiplayerQuoridorGame({ gameId }: MultiplayerQuoridorGameProps
         }
       } else {
         const wallPlacement = move.data as Wall;
-        const nextWalls = [...walls, wallPlacement];
+        const nextWalls = [...walls, { ...wallPlacement, owner: aiPlayerSide }];
         const nextWallsRemaining = {
           ...wallsRemaining,
           [aiPlayerSide]: Math.max(0, (wallsRemaining[aiPlayerSide] ?? 0) - 1),
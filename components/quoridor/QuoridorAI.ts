@@ -1,15 +1,40 @@
 import {
   BOARD_SIZE,
+  GOAL_ROW,
+  MAX_WALLS_PER_PLAYER,
+  GameState,
+  Move,
+  PawnMoveOrder,
   Position,
   Wall,
   PlayerId,
+  applyMove,
+  boardKey,
   buildBlockedEdges,
+  explainMove,
   getValidPawnMoves,
+  getShortestPathLength,
+  getShortestRoute,
   canPlaceWall,
   computeAvailableWalls,
+  countShortestRoutes,
+  deniesBlockingSlot,
+  findBestBlockingWall,
+  getLegalMoves,
+  orderPawnMoves,
   isWinningPosition,
+  positionKey,
+  validateGameState,
 } from './game-logic';
 export type Difficulty = 'easy' | 'medium' | 'hard';
+// Most wall slots strongestCounterBlock tries per position.
+const COUNTER_BLOCK_SLOTS = 16;
+// Score of one step of difference in the race, the base unit of evaluatePosition.
+const RACE_STEP_WEIGHT = 18;
+// Alternative position evaluators for comparing engine strength; 'classic' is the default.
+// 'neutral' drops the south-favouring asymmetries, for analysis that shouldn't lean to either side.
+export type Evaluator = 'classic' | 'distance-only' | 'mobility-heavy' | 'neutral';
+export type MoveQuality = 'good' | 'ok' | 'inaccuracy' | 'blunder';
 
 export interface AIMove {
   type: 'move' | 'wall';
@@ -34,7 +59,39 @@ function mirrorWall(wall: Wall): Wall {
 
 export class QuoridorAI {
   private difficulty: Difficulty;
+  private evaluator: Evaluator = 'classic';
+  // When false the AI only moves its pawn; the opponent's walls are still modelled.
+  private wallsEnabled = true;
+  // When true, pawn moves that let the opponent jump over us for more than the move gains us are skipped
+  // if anything else is legal.
+  private jumpSafetyEnabled = true;
+  // How much hard mode fears the opponent's best reply, from 0 (ignore it) to 1 (assume it is found).
+  private opponentStrength = 1;
+  // Extra penalty per consecutive stalled turn for pawn moves that make no progress while we are not behind,
+  // capped at half a race step so it never outweighs a step lost in the race.
+  private stallPenaltyRate = 4;
+  // How much of the center bonus's base weight (3.5) is gone once the game phase reaches 1.
+  private centerDecay = 2.2;
+  // Off by default: per wall of difference, how strongly wall use is pulled toward the race's progress.
+  private wallPacingWeight = 0;
+  // Forward-progress terms: score += weight * rowsAdvanced ^ exponent, ours added, the opponent's subtracted.
+  private aiAdvanceExponent = 1.55;
+  private aiAdvanceWeight = 3.4;
+  private playerAdvanceExponent = 1.45;
+  private playerAdvanceWeight = 3.2;
+  // Wall bonus for taking away the opponent's only best step.
+  private tempoBonus = 12;
+  // Order our own pawn moves are tried in. Decides ties only where scores carry no noise
+  // (getInstantMove, getMostRestrictingMove, previewMove's reply); getBestMove's noise usually breaks them first.
+  private pawnMoveOrder: PawnMoveOrder = 'directional';
+  // Root candidates and their final scores from the last move, only collected while tracing.
+  private traceEnabled = false;
+  private lastTrace: AIMove[] = [];
+  // Penalty per earlier time a pawn move's resulting position was already played into this game.
+  private repetitionPenalty = 6;
   private gameHistory: { positions: Record<PlayerId, Position>; walls: Wall[] }[] = [];
+  // How often we have moved into each position, keyed by board and side to move.
+  private playedPositions = new Map<string, number>();
 
   constructor(difficulty: Difficulty) {
     this.difficulty = difficulty;
@@ -44,39 +101,118 @@ export class QuoridorAI {
     this.difficulty = difficulty;
   }
 
+  setEvaluator(evaluator: Evaluator) {
+    this.evaluator = evaluator;
+  }
+
+  setWallsEnabled(enabled: boolean) {
+    this.wallsEnabled = enabled;
+  }
+
+  setJumpSafetyEnabled(enabled: boolean) {
+    this.jumpSafetyEnabled = enabled;
+  }
+
+  setOpponentStrength(strength: number) {
+    this.opponentStrength = Math.min(Math.max(strength, 0), 1);
+  }
+
+  setStallPenaltyRate(rate: number) {
+    this.stallPenaltyRate = Math.max(rate, 0);
+  }
+
+  setCenterDecay(decay: number) {
+    this.centerDecay = Math.min(Math.max(decay, 0), 3.5);
+  }
+
+  setWallPacingWeight(weight: number) {
+    this.wallPacingWeight = Math.max(weight, 0);
+  }
+
+  setAdvancementWeights(ai: { exponent: number; weight: number }, player: { exponent: number; weight: number }) {
+    this.aiAdvanceExponent = Math.max(ai.exponent, 0);
+    this.aiAdvanceWeight = Math.max(ai.weight, 0);
+    this.playerAdvanceExponent = Math.max(player.exponent, 0);
+    this.playerAdvanceWeight = Math.max(player.weight, 0);
+  }
+
+  setTempoBonus(bonus: number) {
+    this.tempoBonus = Math.max(bonus, 0);
+  }
+
+  setRepetitionPenalty(penalty: number) {
+    this.repetitionPenalty = Math.max(penalty, 0);
+  }
+
+  setPawnMoveOrder(order: PawnMoveOrder) {
+    this.pawnMoveOrder = order;
+  }
+
+  setTraceEnabled(enabled: boolean) {
+    this.traceEnabled = enabled;
+    this.lastTrace = [];
+  }
+
+  getLastTrace(): AIMove[] {
+    return this.lastTrace;
+  }
+
+  resetHistory() {
+    this.gameHistory = [];
+    this.playedPositions.clear();
+  }
+
   private recordSnapshot(positions: Record<PlayerId, Position>, walls: Wall[]) {
     this.gameHistory.push({ positions: { ...positions }, walls: [...walls] });
   }
 
-  // ---------- utils ----------
-  private getShortestPath(from: Position, targetRow: number, blockedEdges: Set<string>, otherPlayer?: Position): number {
-    const queue = [{ pos: from, dist: 0 }];
-    const visited = new Set<string>();
+  // Positions we move into always leave north to move
+  private recordPlayed(positions: Record<PlayerId, Position>, walls: Wall[], move: AIMove) {
+    const key = move.type === 'move'
+      ? `${boardKey({ ...positions, south: move.data as Position }, walls)}|north`
+      : `${boardKey(positions, [...walls, move.data as Wall])}|north`;
+    this.playedPositions.set(key, (this.playedPositions.get(key) ?? 0) + 1);
+  }
 
-    while (queue.length > 0) {
-      const { pos, dist } = queue.shift()!;
-      const key = `${pos.row},${pos.col}`;
-      if (visited.has(key)) continue;
-      visited.add(key);
-      if (pos.row === targetRow) return dist;
+  private timesPlayed(positions: Record<PlayerId, Position>, walls: Wall[]): number {
+    return this.playedPositions.get(`${boardKey(positions, walls)}|north`) ?? 0;
+  }
 
-      const moves = getValidPawnMoves(pos, otherPlayer || { row: -1, col: -1 }, blockedEdges);
-      for (const move of moves) {
-        const moveKey = `${move.row},${move.col}`;
-        if (!visited.has(moveKey)) queue.push({ pos: move, dist: dist + 1 });
-      }
+  // Consecutive turns, counted back from the latest, in which no wall went down and our route got no shorter
+  private stallStreak(): number {
+    const aiDist = (snapshot: { positions: Record<PlayerId, Position>; walls: Wall[] }) =>
+      this.getShortestPath(snapshot.positions.south, GOAL_ROW.south, buildBlockedEdges(snapshot.walls), snapshot.positions.north);
+
+    let streak = 0;
+    for (let i = this.gameHistory.length - 1; i > 0; i--) {
+      const current = this.gameHistory[i];
+      const previous = this.gameHistory[i - 1];
+      if (current.walls.length !== previous.walls.length || aiDist(current) < aiDist(previous)) break;
+      streak++;
     }
-    return 999;
+    return streak;
+  }
+
+  // ---------- utils ----------
+  private ownPawnMoves(positions: Record<PlayerId, Position>, edges: Set<string>): Position[] {
+    return orderPawnMoves(getValidPawnMoves(positions.south, positions.north, edges), 'south', this.pawnMoveOrder);
+  }
+
+  private getShortestPath(from: Position, targetRow: number, blockedEdges: Set<string>, otherPlayer?: Position): number {
+    return getShortestPathLength(from, targetRow, blockedEdges, otherPlayer);
   }
 
   // ---------- evaluation ----------
   private evaluatePosition(positions: Record<PlayerId, Position>, walls: Wall[]): number {
     const edges = buildBlockedEdges(walls);
-    const aiDist = this.getShortestPath(positions.south, 0, edges, positions.north);
-    const playerDist = this.getShortestPath(positions.north, 8, edges, positions.south);
+    const aiRoute = getShortestRoute(positions.south, GOAL_ROW.south, edges, positions.north);
+    const aiDist = aiRoute ? aiRoute.length : 999;
+    const playerDist = this.getShortestPath(positions.north, GOAL_ROW.north, edges, positions.south);
 
     // Base race evaluation
-    let score = (playerDist - aiDist) * 18; // HARD++ slightly higher base weight
+    let score = (playerDist - aiDist) * RACE_STEP_WEIGHT; // HARD++ slightly higher base weight
+    // Kept at the classic race weight so randomness and wall bonuses stay on the same scale
+    if (this.evaluator === 'distance-only') return score;
 
     // Positional factors
     const aiRow = positions.south.row;
@@ -84,22 +220,42 @@ export class QuoridorAI {
     const playerRow = positions.north.row;
     const playerCol = positions.north.col;
 
-    // Phase (0..1)
+    // Phase (0..1): walls placed or distance the leading pawn has run, whichever is further along
     const totalWalls = walls.length;
-    const gamePhase = Math.min(totalWalls / 20, 1);
+    const rowSpan = BOARD_SIZE - 1;
+    const aiRun = rowSpan - Math.abs(aiRow - GOAL_ROW.south);
+    const playerRun = rowSpan - Math.abs(playerRow - GOAL_ROW.north);
+    const raceProgress = Math.max(aiRun, playerRun) / rowSpan;
+    const gamePhase = Math.min(Math.max(totalWalls / 20, raceProgress), 1);
+
+    if (this.evaluator === 'neutral') {
+      // One set of weights for both sides, so mirror-image positions score exactly opposite
+      const sideScore = (pos: Position, progress: number, mobility: number) =>
+        (4 - Math.abs(pos.col - 4)) * (3.5 - gamePhase * this.centerDecay) + Math.pow(progress, 1.5) * 3.3 + mobility * 2.4;
+      return score +
+        sideScore(positions.south, 8 - aiRow, getValidPawnMoves(positions.south, positions.north, edges).length) -
+        sideScore(positions.north, playerRow, getValidPawnMoves(positions.north, positions.south, edges).length);
+    }
 
-    // Center control bonus, stronger early; also slight corridor bonus (|col-4| small)
-    const centerWeight = 3.5 - gamePhase * 2.2; // HARD++ sharper decay
-    score += (4 - Math.abs(aiCol - 4)) * centerWeight;
+    // When most of our best route runs down an edge column, don't pull the pawn off it toward the center
+    const huggingEdge = !!aiRoute && aiRoute.length > 0 &&
+      aiRoute.filter(pos => pos.col === 0 || pos.col === BOARD_SIZE - 1).length * 2 >= aiRoute.length;
 
-    // Forward progress (concave for AI, convex penalty for player)
-    score += Math.pow(8 - aiRow, 1.55) * 3.4;
-    score -= Math.pow(playerRow, 1.45) * 3.2;
+    // Center control bonus, stronger early; also slight corridor bonus (|col-4| small).
+    // On an edge route every column gets the full bonus, so the column stops mattering.
+    const centerWeight = 3.5 - gamePhase * this.centerDecay;
+    score += (huggingEdge ? 4 : 4 - Math.abs(aiCol - 4)) * centerWeight;
+
+    // Forward progress. With the default exponents and weights a row we advance scores more than a
+    // row the opponent advances, and the gap widens the further both pawns have come, so an even
+    // exchange of steps nets us a small gain.
+    score += Math.pow(8 - aiRow, this.aiAdvanceExponent) * this.aiAdvanceWeight;
+    score -= Math.pow(playerRow, this.playerAdvanceExponent) * this.playerAdvanceWeight;
 
     // Mobility differential
     const aiMoves = getValidPawnMoves(positions.south, positions.north, edges);
     const playerMoves = getValidPawnMoves(positions.north, positions.south, edges);
-    score += (aiMoves.length - playerMoves.length) * 2.4;
+    score += (aiMoves.length - playerMoves.length) * (this.evaluator === 'mobility-heavy' ? 7.2 : 2.4);
 
     // Proximity heuristic (close & ahead encourages blocking traps)
     const distToOpponent = Math.abs(aiRow - playerRow) + Math.abs(aiCol - playerCol);
@@ -113,9 +269,11 @@ export class QuoridorAI {
       score += (playerMoves.length - aiMoves.length) * 3.6;
       if (totalWalls > 10) score += (playerDist - aiDist) * 6.0;
 
-      // Edge/corner aversion for AI (prefer central corridors)
-      if (aiCol === 0 || aiCol === 8) score -= 6;
-      if (aiCol === 1 || aiCol === 7) score -= 3;
+      // Edge/corner aversion for AI (prefer central corridors), unless the edge is where our route goes
+      if (!huggingEdge) {
+        if (aiCol === 0 || aiCol === 8) score -= 6;
+        if (aiCol === 1 || aiCol === 7) score -= 3;
+      }
 
       // Bonus for creating "choke" near opponent (if our col ~= opponent col within 1)
       if (Math.abs(aiCol - playerCol) <= 1 && aiRow < playerRow) score += 5;
@@ -124,25 +282,139 @@ export class QuoridorAI {
     return score;
   }
 
+  // True when stepping to `move` lets the opponent jump over us and gain more on their route than the
+  // step gains on ours. `aiDist` and `opponentDist` are both path lengths before the move.
+  private jumpLosesExchange(
+    positions: Record<PlayerId, Position>,
+    move: Position,
+    edges: Set<string>,
+    aiDist: number,
+    opponentDist: number,
+  ): boolean {
+    const jumps = getValidPawnMoves(positions.north, move, edges)
+      .filter(to => Math.abs(to.row - positions.north.row) + Math.abs(to.col - positions.north.col) === 2);
+    if (jumps.length === 0) return false;
+
+    const afterJump = Math.min(...jumps.map(to => 1 + this.getShortestPath(to, GOAL_ROW.north, edges, move)));
+    const opponentGain = opponentDist - afterJump;
+    const ourGain = aiDist - this.getShortestPath(move, GOAL_ROW.south, edges, positions.north);
+    return opponentGain > 0 && opponentGain > ourGain;
+  }
+
   // ---------- walls ----------
   private isBlockingWall(wall: Wall, positions: Record<PlayerId, Position>, walls: Wall[]): number {
     const currentEdges = buildBlockedEdges(walls);
-    const currentPlayerDist = this.getShortestPath(positions.north, 8, currentEdges, positions.south);
+    const currentPlayerDist = this.getShortestPath(positions.north, GOAL_ROW.north, currentEdges, positions.south);
     const testWalls = [...walls, wall];
     const testEdges = buildBlockedEdges(testWalls);
-    const newPlayerDist = this.getShortestPath(positions.north, 8, testEdges, positions.south);
+    const newPlayerDist = this.getShortestPath(positions.north, GOAL_ROW.north, testEdges, positions.south);
     return newPlayerDist - currentPlayerDist;
   }
 
-  private getWallValue(wall: Wall, positions: Record<PlayerId, Position>, walls: Wall[], wallsRemaining: number): number {
+  // A wall that costs the opponent nothing can still be worth playing if it takes
+  // away a slot the opponent could use to lengthen our own route.
+  private protectsOwnPath(wall: Wall, positions: Record<PlayerId, Position>, walls: Wall[]): boolean {
+    return deniesBlockingSlot('south', wall, positions, walls);
+  }
+
+  private opponentWinsNext(positions: Record<PlayerId, Position>, walls: Wall[]): boolean {
+    return getValidPawnMoves(positions.north, positions.south, buildBlockedEdges(walls))
+      .some(move => isWinningPosition('north', move));
+  }
+
+  // Easy mode may play inaccurately but not hand over the game: if `chosen` lets the opponent step
+  // onto their goal next turn, swap in the best-evaluated move that doesn't, when one exists.
+  private avoidImmediateLoss(
+    chosen: AIMove,
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: number,
+  ): AIMove {
+    const after = (move: AIMove) => move.type === 'move'
+      ? { positions: { ...positions, south: move.data as Position }, walls }
+      : { positions, walls: [...walls, move.data as Wall] };
+    const chosenAfter = after(chosen);
+    if (!this.opponentWinsNext(chosenAfter.positions, chosenAfter.walls)) return chosen;
+
+    const candidates: AIMove[] = this.ownPawnMoves(positions, buildBlockedEdges(walls))
+      .map((move): AIMove => ({ type: 'move', data: move, score: 0 }));
+    if (this.wallsEnabled && wallsRemaining > 0) {
+      computeAvailableWalls('horizontal', walls, positions)
+        .concat(computeAvailableWalls('vertical', walls, positions))
+        .forEach(wall => candidates.push({ type: 'wall', data: wall, score: 0 }));
+    }
+
+    let rescue: AIMove | null = null;
+    for (const candidate of candidates) {
+      const next = after(candidate);
+      if (this.opponentWinsNext(next.positions, next.walls)) continue;
+      const score = this.evaluatePosition(next.positions, next.walls);
+      if (!rescue || score > rescue.score) rescue = { ...candidate, score };
+    }
+    return rescue ?? chosen;
+  }
+
+  // Roughly findBestBlockingWall('north', ...).increase at a bounded cost: only the slots touching our
+  // current route are tried, nearest our pawn first, and only the first COUNTER_BLOCK_SLOTS legal ones.
+  // A wall away from the route leaves that route open, so it cannot lengthen our path anyway.
+  private strongestCounterBlock(positions: Record<PlayerId, Position>, walls: Wall[]): number {
+    const edges = buildBlockedEdges(walls);
+    const route = getShortestRoute(positions.south, GOAL_ROW.south, edges, positions.north);
+    if (!route) return 0;
+
+    // Walk the route collecting the wall slots whose 2x2 block holds each square, legality checked last
+    const slots: Wall[] = [];
+    const tried = new Set<string>();
+    for (const sq of [positions.south, ...route]) {
+      if (slots.length >= COUNTER_BLOCK_SLOTS) break;
+      for (const row of [sq.row - 1, sq.row]) {
+        for (const col of [sq.col - 1, sq.col]) {
+          if (row < 0 || col < 0 || row > BOARD_SIZE - 2 || col > BOARD_SIZE - 2) continue;
+          for (const orientation of ['horizontal', 'vertical'] as const) {
+            const key = `${orientation[0]}${row},${col}`;
+            if (tried.has(key) || slots.length >= COUNTER_BLOCK_SLOTS) continue;
+            tried.add(key);
+            const wall: Wall = { row, col, orientation };
+            if (canPlaceWall(wall, walls, positions)) slots.push(wall);
+          }
+        }
+      }
+    }
+
+    const before = route.length;
+    let strongest = 0;
+    for (const wall of slots) {
+      const after = this.getShortestPath(positions.south, GOAL_ROW.south, buildBlockedEdges([...walls, wall]), positions.north);
+      strongest = Math.max(strongest, after - before);
+    }
+    return strongest;
+  }
+
+  // The opponent's next step along their shortest route, or null when several steps are equally good
+  private opponentBestStep(positions: Record<PlayerId, Position>, edges: Set<string>): Position | null {
+    const steps = getValidPawnMoves(positions.north, positions.south, edges)
+      .map(step => ({ step, dist: this.getShortestPath(step, GOAL_ROW.north, edges, positions.south) }))
+      .sort((a, b) => a.dist - b.dist);
+    if (steps.length === 0 || (steps.length > 1 && steps[1].dist === steps[0].dist)) return null;
+    return steps[0].step;
+  }
+
+  // opponentStep is opponentBestStep for the position before the wall, computed once by the caller
+  private getWallValue(
+    wall: Wall,
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: number,
+    opponentStep: Position | null,
+  ): number {
     let value = 0;
 
     // Check if wall blocks AI's own path - heavily penalize
     const currentEdges = buildBlockedEdges(walls);
-    const currentAiDist = this.getShortestPath(positions.south, 0, currentEdges, positions.north);
+    const currentAiDist = this.getShortestPath(positions.south, GOAL_ROW.south, currentEdges, positions.north);
     const testWalls = [...walls, wall];
     const testEdges = buildBlockedEdges(testWalls);
-    const newAiDist = this.getShortestPath(positions.south, 0, testEdges, positions.north);
+    const newAiDist = this.getShortestPath(positions.south, GOAL_ROW.south, testEdges, positions.north);
     
     // Heavily penalize walls that increase AI's distance
     if (newAiDist > currentAiDist) {
@@ -153,6 +425,16 @@ export class QuoridorAI {
     const blockingValue = this.isBlockingWall(wall, positions, walls);
     value += blockingValue * 20;
 
+    // Tempo: taking away the opponent's only best step makes them spend a move rerouting
+    if (opponentStep && !getValidPawnMoves(positions.north, positions.south, testEdges)
+      .some(move => positionKey(move) === positionKey(opponentStep))) {
+      value += this.tempoBonus;
+    }
+
+    // Flexibility: between otherwise similar walls, prefer the one leaving us more shortest routes.
+    // At most +2, below the smallest step of the blocking and proximity terms, so it only breaks ties.
+    value += countShortestRoutes(positions.south, GOAL_ROW.south, testEdges, 4) * 0.5;
+
     // Distances to actors
     const distToPlayer = Math.abs(wall.row - positions.north.row) + Math.abs(wall.col - positions.north.col);
     const distToAI = Math.abs(wall.row - positions.south.row) + Math.abs(wall.col - positions.south.col);
@@ -182,8 +464,8 @@ export class QuoridorAI {
     // HARD++ predictive/phase logic
     if (this.difficulty === 'hard') {
       const currentEdges = buildBlockedEdges(walls);
-      const aiDist = this.getShortestPath(positions.south, 0, currentEdges, positions.north);
-      const playerDist = this.getShortestPath(positions.north, 8, currentEdges, positions.south);
+      const aiDist = this.getShortestPath(positions.south, GOAL_ROW.south, currentEdges, positions.north);
+      const playerDist = this.getShortestPath(positions.north, GOAL_ROW.north, currentEdges, positions.south);
       const totalMoves = positions.south.row + (8 - positions.north.row);
       const gamePhase = Math.min(totalMoves / 16, 1);
 
@@ -221,7 +503,7 @@ export class QuoridorAI {
       }
 
       // Wall conservation - don't waste walls early
-      const wallRatio = wallsRemaining / 10;
+      const wallRatio = wallsRemaining / MAX_WALLS_PER_PLAYER;
       if (gamePhase < 0.4 && wallRatio > 0.6 && blockingValue < 2) value -= 45;
       
       // Late game - be more aggressive with remaining walls
@@ -247,7 +529,7 @@ export class QuoridorAI {
       const key = `${pos.row},${pos.col}`;
       if (visited.has(key)) continue;
       visited.add(key);
-      if (pos.row === 8) return path;
+      if (pos.row === GOAL_ROW.north) return path;
 
       const moves = getValidPawnMoves(pos, positions.south, edges);
       // Greedy toward the goal row
@@ -263,15 +545,15 @@ export class QuoridorAI {
     if (wallsRemaining === 0) return false;
 
     const edges = buildBlockedEdges(walls);
-    const aiDist = this.getShortestPath(positions.south, 0, edges, positions.north);
-    const playerDist = this.getShortestPath(positions.north, 8, edges, positions.south);
+    const aiDist = this.getShortestPath(positions.south, GOAL_ROW.south, edges, positions.north);
+    const playerDist = this.getShortestPath(positions.north, GOAL_ROW.north, edges, positions.south);
     const totalMoves = positions.south.row + (8 - positions.north.row);
     const gamePhase = Math.min(totalMoves / 16, 1);
 
     if (this.difficulty === 'easy') return Math.random() < 0.25;
 
     if (this.difficulty === 'hard') {
-      const wallRatio = wallsRemaining / 10;
+      const wallRatio = wallsRemaining / MAX_WALLS_PER_PLAYER;
       const distanceDiff = playerDist - aiDist;
 
       // Early game - very conservative, only if opponent is close and we're behind
@@ -331,9 +613,9 @@ export class QuoridorAI {
 
     // Consider a handful of top opponent walls if any remain
     if (oppWallsRemaining > 0) {
+      // computeAvailableWalls already runs the full canPlaceWall check on every slot
       const allOppWalls = computeAvailableWalls('horizontal', walls, positions)
-        .concat(computeAvailableWalls('vertical', walls, positions))
-        .filter(w => canPlaceWall(w, walls, positions));
+        .concat(computeAvailableWalls('vertical', walls, positions));
 
       // rank by how much they hurt us (inverse of our eval)
       const scored = allOppWalls
@@ -354,47 +636,98 @@ export class QuoridorAI {
   }
 
   // ---------- move selection ----------
+  // Null when the position fails validateGameState or south has no legal move; callers should stop
+  // asking rather than retry, since the same position gives the same answer.
   async getBestMove(
     positions: Record<PlayerId, Position>,
     walls: Wall[],
     wallsRemaining: Record<PlayerId, number>
   ): Promise<AIMove | null> {
-    return this.getBestMoveFromTypeScript(positions, walls, wallsRemaining.south);
+    return this.getBestMoveFromTypeScript(positions, walls, wallsRemaining.south, wallsRemaining.north);
+  }
+
+  // getBestMove plus a coaching line for it. Call it instead of getBestMove rather than after it,
+  // since both record the position in the game history.
+  async explainBestMove(
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: Record<PlayerId, number>
+  ): Promise<{ move: AIMove; reason: string } | null> {
+    const move = await this.getBestMove(positions, walls, wallsRemaining);
+    if (!move) return null;
+
+    const played: Move = move.type === 'move'
+      ? { type: 'move', data: move.data as Position }
+      : { type: 'wall', data: move.data as Wall };
+    return { move, reason: explainMove('south', played, positions, walls, wallsRemaining) };
   }
 
   private getBestMoveFromTypeScript(
     positions: Record<PlayerId, Position>,
     walls: Wall[],
-    wallsRemaining: number
+    wallsRemaining: number,
+    opponentWallsRemaining = MAX_WALLS_PER_PLAYER
   ): AIMove | null {
+    // Reserves come from stored game state; keep a corrupt value from inflating the wall search or ratios
+    if (wallsRemaining < 0 || wallsRemaining > MAX_WALLS_PER_PLAYER) {
+      console.warn(`[QuoridorAI] Clamping wall reserve ${wallsRemaining} to 0..${MAX_WALLS_PER_PLAYER}`);
+      wallsRemaining = Math.min(Math.max(wallsRemaining, 0), MAX_WALLS_PER_PLAYER);
+    }
+
+    const invalidReason = validateGameState(positions, walls);
+    if (invalidReason) {
+      console.warn(`[QuoridorAI] Refusing to move from an invalid state: ${invalidReason}`);
+      return null;
+    }
+
     this.recordSnapshot(positions, walls);
     const edges = buildBlockedEdges(walls);
-    const aiMoves = getValidPawnMoves(positions.south, positions.north, edges);
+    const aiMoves = this.ownPawnMoves(positions, edges);
     let bestMove: AIMove | null = null;
+    if (this.traceEnabled) this.lastTrace = [];
 
     // Check for immediate win
     for (const move of aiMoves) {
       if (isWinningPosition('south', move)) {
-        return { type: 'move', data: move, score: 1000 };
+        const winningMove: AIMove = { type: 'move', data: move, score: 1000 };
+        if (this.traceEnabled) this.lastTrace.push(winningMove);
+        return winningMove;
       }
     }
 
-    // Try pawn moves
-    for (const move of aiMoves) {
+    // Try pawn moves, leaving out ones that hand the opponent a jump worth more than the step, unless
+    // nothing else is legal
+    const currentAiDist = this.getShortestPath(positions.south, GOAL_ROW.south, edges, positions.north);
+    const currentPlayerDist = this.getShortestPath(positions.north, GOAL_ROW.north, edges, positions.south);
+    const notBehind = currentAiDist <= currentPlayerDist;
+    const stallPenalty = notBehind
+      ? Math.min(this.stallStreak() * this.stallPenaltyRate, RACE_STEP_WEIGHT / 2)
+      : 0;
+    const safeMoves = this.jumpSafetyEnabled
+      ? aiMoves.filter(move => !this.jumpLosesExchange(positions, move, edges, currentAiDist, currentPlayerDist))
+      : aiMoves;
+    for (const move of safeMoves.length > 0 ? safeMoves : aiMoves) {
       const testPos = { ...positions, south: move };
       let score = this.evaluatePosition(testPos, walls);
 
+      // Small nudge away from shuffling back into positions already played this game
+      score -= this.timesPlayed(testPos, walls) * this.repetitionPenalty;
+
+      // Growing push to advance or commit a wall after several turns of neither
+      if (stallPenalty > 0 && this.getShortestPath(move, GOAL_ROW.south, edges, positions.north) >= currentAiDist) {
+        score -= stallPenalty;
+      }
+
       // HARD++: look at opponent reply (minimax-ish, depth 1)
       if (this.difficulty === 'hard') {
-        const oppReply = this.opponentBestReplyScore(testPos, walls, /*oppWallsRemaining*/ 10); // assume opponent has some walls; adjust if you track it
+        const oppReply = this.opponentBestReplyScore(testPos, walls, opponentWallsRemaining);
         // We want to avoid positions where opponent reply improves their eval.
         const replyPenalty = Math.max(0, oppReply - score);
-        score = score - 0.85 * replyPenalty;
+        score = score - 0.85 * this.opponentStrength * replyPenalty;
 
         // Strong progress incentive - heavily penalize backward or non-progress moves
         const newEdges = buildBlockedEdges(walls);
-        const newAiDist = this.getShortestPath(move, 0, newEdges, testPos.north);
-        const currentAiDist = this.getShortestPath(positions.south, 0, edges, positions.north);
+        const newAiDist = this.getShortestPath(move, GOAL_ROW.south, newEdges, testPos.north);
         
         // Penalize moves that don't improve distance to goal
         if (newAiDist > currentAiDist) score -= 50;
@@ -413,23 +746,37 @@ export class QuoridorAI {
       }
 
       score = this.addRandomness(score);
+      if (this.traceEnabled) this.lastTrace.push({ type: 'move', data: move, score });
       if (!bestMove || score > bestMove.score) bestMove = { type: 'move', data: move, score };
     }
 
     // Consider walls
-    if (wallsRemaining > 0 && this.shouldUseWall(positions, walls, wallsRemaining)) {
+    if (this.wallsEnabled && wallsRemaining > 0 && this.shouldUseWall(positions, walls, wallsRemaining)) {
       const allWalls = computeAvailableWalls('horizontal', walls, positions)
         .concat(computeAvailableWalls('vertical', walls, positions));
 
+      const opponentStep = this.opponentBestStep(positions, edges);
       const strategicWalls = allWalls
-        .filter(wall => canPlaceWall(wall, walls, positions))
-        .map(wall => ({ wall, value: this.getWallValue(wall, positions, walls, wallsRemaining) }))
+        // Walls the opponent routes around for free are wasted, however close to them they sit
+        .filter(wall => this.isBlockingWall(wall, positions, walls) > 0 || this.protectsOwnPath(wall, positions, walls))
+        .map(wall => ({ wall, value: this.getWallValue(wall, positions, walls, wallsRemaining, opponentStep) }))
         .sort((a, b) => b.value - a.value);
 
       const wallsToCheck = this.difficulty === 'easy' ? strategicWalls.slice(0, 3) :
         this.difficulty === 'medium' ? strategicWalls.slice(0, 12) :
           strategicWalls.slice(0, 18); // HARD++ consider a few more
 
+      // Pacing: how many walls we are behind (+) or ahead of (-) spending in step with the race,
+      // taking the game as done when the pawn nearer its goal arrives
+      const nearerDist = Math.min(currentAiDist, this.getShortestPath(positions.north, GOAL_ROW.north, edges, positions.south));
+      const raceDone = 1 - Math.min(nearerDist, BOARD_SIZE - 1) / (BOARD_SIZE - 1);
+      const wallPacing = raceDone * MAX_WALLS_PER_PLAYER - (MAX_WALLS_PER_PLAYER - wallsRemaining);
+
+      // The opponent's strongest block on our route right now, to compare each candidate against.
+      // Skipped entirely when they have no walls left to block with.
+      const checkCounterBlock = this.difficulty === 'hard' && opponentWallsRemaining > 0;
+      const counterBlockBefore = checkCounterBlock ? this.strongestCounterBlock(positions, walls) : 0;
+
       for (const { wall } of wallsToCheck) {
         const withWall = [...walls, wall];
         let score = this.evaluatePosition(positions, withWall);
@@ -441,17 +788,26 @@ export class QuoridorAI {
           if (inc >= 3) score += 50;
 
           // Consider opponent reply after our wall
-          const oppReply = this.opponentBestReplyScore(positions, withWall, /*oppWallsRemaining*/ 9);
+          const oppReply = this.opponentBestReplyScore(positions, withWall, opponentWallsRemaining);
           const replyPenalty = Math.max(0, oppReply - score);
-          score = score - 0.7 * replyPenalty;
+          score = score - 0.7 * this.opponentStrength * replyPenalty;
 
           // Avoid walls that also hurt us (recompute aiDist swing)
-          const aiDistBefore = this.getShortestPath(positions.south, 0, buildBlockedEdges(walls), positions.north);
-          const aiDistAfter = this.getShortestPath(positions.south, 0, buildBlockedEdges(withWall), positions.north);
+          const aiDistBefore = this.getShortestPath(positions.south, GOAL_ROW.south, buildBlockedEdges(walls), positions.north);
+          const aiDistAfter = this.getShortestPath(positions.south, GOAL_ROW.south, buildBlockedEdges(withWall), positions.north);
           if (aiDistAfter > aiDistBefore) score -= 14;
+
+          // One opponent wall of lookahead: avoid walls that set up a much stronger block on us
+          if (checkCounterBlock) {
+            const counterBlock = this.strongestCounterBlock(positions, withWall);
+            if (counterBlock - counterBlockBefore >= 2) score -= (counterBlock - counterBlockBefore) * 12;
+          }
         }
 
+        score += this.wallPacingWeight * wallPacing;
+
         score = this.addRandomness(score);
+        if (this.traceEnabled) this.lastTrace.push({ type: 'wall', data: wall, score });
 
         // if (this.difficulty === 'hard' && bestMove && bestMove.type === 'move' && score <= bestMove.score + 2) {
         //   // Only choose wall if it's significantly better than moving forward
@@ -464,7 +820,119 @@ export class QuoridorAI {
       }
     }
 
-    return bestMove;
+    const chosen = this.difficulty === 'easy' && bestMove
+      ? this.avoidImmediateLoss(bestMove, positions, walls, wallsRemaining)
+      : bestMove;
+    if (chosen) this.recordPlayed(positions, walls, chosen);
+    return chosen;
+  }
+
+  // "Cramp the opponent": the legal move, pawn or wall, that leaves north the fewest pawn moves, with
+  // ties broken by evaluatePosition. No noise, so the same position always gives the same answer.
+  getMostRestrictingMove(
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: number,
+  ): AIMove | null {
+    const candidates: { move: AIMove; next: Record<PlayerId, Position>; nextWalls: Wall[] }[] = [];
+    for (const move of this.ownPawnMoves(positions, buildBlockedEdges(walls))) {
+      candidates.push({ move: { type: 'move', data: move, score: 0 }, next: { ...positions, south: move }, nextWalls: walls });
+    }
+    if (this.wallsEnabled && wallsRemaining > 0) {
+      const legalWalls = computeAvailableWalls('horizontal', walls, positions)
+        .concat(computeAvailableWalls('vertical', walls, positions));
+      for (const wall of legalWalls) {
+        candidates.push({ move: { type: 'wall', data: wall, score: 0 }, next: positions, nextWalls: [...walls, wall] });
+      }
+    }
+
+    let best: AIMove | null = null;
+    let bestMobility = Infinity;
+    for (const { move, next, nextWalls } of candidates) {
+      const mobility = getValidPawnMoves(next.north, next.south, buildBlockedEdges(nextWalls)).length;
+      const score = this.evaluatePosition(next, nextWalls);
+      if (!best || mobility < bestMobility || (mobility === bestMobility && score > best.score)) {
+        best = { ...move, score };
+        bestMobility = mobility;
+      }
+    }
+    return best;
+  }
+
+  // Heuristic-only move for very slow devices: no evaluation, noise or reply model. Steps along the
+  // shortest route, or blocks when the opponent is two or more steps ahead and the wall costs us less.
+  getInstantMove(
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: number,
+  ): AIMove | null {
+    const edges = buildBlockedEdges(walls);
+    const aiDist = this.getShortestPath(positions.south, GOAL_ROW.south, edges, positions.north);
+    const playerDist = this.getShortestPath(positions.north, GOAL_ROW.north, edges, positions.south);
+
+    if (this.wallsEnabled && wallsRemaining > 0 && playerDist + 2 <= aiDist) {
+      const block = findBestBlockingWall('south', positions, walls);
+      if (block) {
+        const withWall = buildBlockedEdges([...walls, block.wall]);
+        const cost = this.getShortestPath(positions.south, GOAL_ROW.south, withWall, positions.north) - aiDist;
+        if (block.increase > cost) return { type: 'wall', data: block.wall, score: block.increase - cost };
+      }
+    }
+
+    let best: AIMove | null = null;
+    for (const move of this.ownPawnMoves(positions, edges)) {
+      const score = -this.getShortestPath(move, GOAL_ROW.south, edges, positions.north);
+      if (!best || score > best.score) best = { type: 'move', data: move, score };
+    }
+    return best;
+  }
+
+  // How much worse a south wall is than south's best move by evaluatePosition, without noise. Thresholds
+  // are in steps of the race term (18 per step of path difference). Null when the wall is illegal.
+  reviewWall(
+    positions: Record<PlayerId, Position>,
+    walls: Wall[],
+    wallsRemaining: number,
+    wall: Wall,
+  ): { delta: number; quality: MoveQuality } | null {
+    if (wallsRemaining <= 0 || !canPlaceWall(wall, walls, positions)) return null;
+
+    const edges = buildBlockedEdges(walls);
+    let bestScore = -Infinity;
+    for (const move of getValidPawnMoves(positions.south, positions.north, edges)) {
+      bestScore = Math.max(bestScore, this.evaluatePosition({ ...positions, south: move }, walls));
+    }
+    const candidates = computeAvailableWalls('horizontal', walls, positions)
+      .concat(computeAvailableWalls('vertical', walls, positions));
+    for (const candidate of candidates) {
+      bestScore = Math.max(bestScore, this.evaluatePosition(positions, [...walls, candidate]));
+    }
+
+    const delta = Math.max(0, bestScore - this.evaluatePosition(positions, [...walls, wall]));
+    const quality: MoveQuality = delta < 9 ? 'good' : delta < 18 ? 'ok' : delta < 36 ? 'inaccuracy' : 'blunder';
+    return { delta, quality };
+  }
+
+  // What-if for a hovered move: the evaluation (from south's side) once `move` is played from `state`,
+  // and the reply that evaluation likes best for the side to move next. Null when `move` is illegal.
+  previewMove(state: GameState, move: Move): { score: number; reply: Move | null } | null {
+    const next = applyMove(state, move);
+    if (!next) return null;
+
+    const sign = next.toMove === 'south' ? 1 : -1;
+    let reply: Move | null = null;
+    let replyScore = -Infinity;
+    for (const candidate of getLegalMoves(next, this.pawnMoveOrder)) {
+      const after = applyMove(next, candidate);
+      if (!after) continue;
+      const candidateScore = sign * this.evaluatePosition(after.positions, after.walls);
+      if (candidateScore > replyScore) {
+        reply = candidate;
+        replyScore = candidateScore;
+      }
+    }
+
+    return { score: this.evaluatePosition(next.positions, next.walls), reply };
   }
 
   getBestMoveForSide(
@@ -472,10 +940,11 @@ export class QuoridorAI {
     positions: Record<PlayerId, Position>,
     walls: Wall[],
     wallsRemaining: number,
+    opponentWallsRemaining = MAX_WALLS_PER_PLAYER,
   ): AIMove | null {
     // if (side === 'south') {
     //   const wallsRemainingRecord = { north: 10, south: wallsRemaining };
-      return this.getBestMoveFromTypeScript(positions, walls, wallsRemaining);
+      return this.getBestMoveFromTypeScript(positions, walls, wallsRemaining, opponentWallsRemaining);
     // }
 
     // const mirroredPositions: Record<PlayerId, Position> = {
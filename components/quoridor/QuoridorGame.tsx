@@ -84,6 +84,8 @@ export function QuoridorGame() {
   const [winner, setWinner] = useState<PlayerId | null>(null);
   const [statusMessage, setStatusMessage] = useState<string | null>(null);
   const [isAiThinking, setIsAiThinking] = useState(false);
+  // Set when the AI returns no move, so its turn is not retried until a new game starts
+  const [aiStuck, setAiStuck] = useState(false);
   const [difficulty, setDifficulty] = useState<Difficulty>('medium');
   const [ai] = useState(() => new QuoridorAI('medium'));
   const [showHelp, setShowHelp] = useState(false);
@@ -134,13 +136,13 @@ export function QuoridorGame() {
     const moveCount = (8 + positions.north.row) - positions.south.row;
     let timer: number | null = null;
     
-    if (positions.north.row === 0 && wallsRemaining[currentPlayer] == 10) {
+    if (positions.north.row === 0 && wallsRemaining[currentPlayer] === MAX_WALLS_PER_PLAYER) {
       setShowHelp(true);
       if (currentPlayer === 'north') {
         setHelpMessage('🎯 Reach the top row. Click a square to move.');
       }
       timer = setTimeout(() => setHelpMessage(''), 1500);
-    } else if ( 3 <= moveCount && moveCount <= 8 && currentPlayer === 'north' && wallsRemaining[currentPlayer] == 10) {
+    } else if ( 3 <= moveCount && moveCount <= 8 && currentPlayer === 'north' && wallsRemaining[currentPlayer] === MAX_WALLS_PER_PLAYER) {
       setShowHelp(true);
       setHelpMessage('🧱 Click "Place wall" then tap a highlighted area.');
       timer = setTimeout(() => setHelpMessage(''), 1500);
@@ -161,7 +163,7 @@ export function QuoridorGame() {
   }, [difficulty, ai]);
 
   const makeAiMove = () => {
-    if (currentPlayer !== 'south' || winner || isAiThinking) return;
+    if (currentPlayer !== 'south' || winner || isAiThinking || aiStuck) return;
 
     setIsAiThinking(true);
 
@@ -180,11 +182,14 @@ export function QuoridorGame() {
               setCurrentPlayer('north');
             }
           } else {
-            setWalls(prev => [...prev, bestMove.data as Wall]);
+            setWalls(prev => [...prev, { ...(bestMove.data as Wall), owner: 'south' }]);
             setWallsRemaining(prev => ({...prev, south: prev.south - 1}));
             soundManager.playWallPlace();
             setCurrentPlayer('north');
           }
+        } else {
+          setAiStuck(true);
+          setStatusMessage('The computer cannot move from this position. Start a new game to keep playing.');
         }
       } catch (error) {
         console.warn('[Quoridor] Failed to resolve AI move', error);
@@ -197,10 +202,10 @@ export function QuoridorGame() {
 
   // Trigger AI move when it's AI's turn
   useEffect(() => {
-    if (currentPlayer === 'south' && !winner && !isAiThinking) {
+    if (currentPlayer === 'south' && !winner && !isAiThinking && !aiStuck) {
       makeAiMove();
     }
-  }, [currentPlayer, winner, isAiThinking]);
+  }, [currentPlayer, winner, isAiThinking, aiStuck]);
 
   const controlPalette = useMemo(
     () => ({
@@ -370,8 +375,10 @@ export function QuoridorGame() {
     setWinner(null);
     setStatusMessage(null);
     setIsAiThinking(false);
+    setAiStuck(false);
     setShowHelp(true);
     setHelpMessage('');
+    ai.resetHistory();
   };
 
   const handleDifficultyChange = (newDifficulty: Difficulty) => {
@@ -439,7 +446,7 @@ export function QuoridorGame() {
       return;
     }
 
-    setWalls((existing) => [...existing, wall]);
+    setWalls((existing) => [...existing, { ...wall, owner: currentPlayer }]);
     setWallsRemaining((remaining) => ({
       ...remaining,
       [currentPlayer]: remaining[currentPlayer] - 1,
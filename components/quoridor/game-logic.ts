@@ -12,16 +12,32 @@ export type Wall = {
   row: number;
   col: number;
   orientation: Orientation;
+  // Who placed the wall; absent for walls from older saved games.
+  owner?: PlayerId;
 };
 
 export type PlayerId = 'north' | 'south';
 
+// Pawn move list order; decides ties only in noise-free searches. 'directional' is getValidPawnMoves' own.
+export type PawnMoveOrder = 'directional' | 'row-major' | 'forward-first' | 'center-first';
+
+export type GameState = {
+  positions: Record<PlayerId, Position>;
+  walls: Wall[];
+  wallsRemaining: Record<PlayerId, number>;
+  toMove: PlayerId;
+};
+
+export type Move =
+  | { type: 'move'; data: Position }
+  | { type: 'wall'; data: Wall };
+
 export const INITIAL_POSITIONS: Record<PlayerId, Position> = {
   north: { row: 0, col: Math.floor(BOARD_SIZE / 2) },
   south: { row: BOARD_SIZE - 1, col: Math.floor(BOARD_SIZE / 2) },
 };
 
-const GOAL_ROW: Record<PlayerId, number> = {
+export const GOAL_ROW: Record<PlayerId, number> = {
   north: BOARD_SIZE - 1,
   south: 0,
 };
@@ -114,6 +130,22 @@ function getAdjacentPositions({ row, col }: Position): Position[] {
   );
 }
 
+// Where a pawn lands moving onto the opponent: straight over, else diagonally (the edge counts as a wall).
+export function getJumpLandings(adjacent: Position, delta: Position, blockedEdges: Set<string>): Position[] {
+  const canStepTo = (target: Position) => isWithinBounds(target) && !isEdgeBlocked(adjacent, target, blockedEdges);
+
+  const jump = { row: adjacent.row + delta.row, col: adjacent.col + delta.col };
+  if (canStepTo(jump)) {
+    return [jump];
+  }
+
+  const diagonals = PERPENDICULARS[`${delta.row},${delta.col}`] ?? [];
+  return diagonals
+    .map((diagDelta) => ({ row: adjacent.row + diagDelta.row, col: adjacent.col + diagDelta.col }))
+    .filter(canStepTo);
+}
+
+// Moves in DIRECTIONS order, with jump landings standing in for the opponent's square.
 export function getValidPawnMoves(
   current: Position,
   opponent: Position,
@@ -127,27 +159,9 @@ export function getValidPawnMoves(
       continue;
     }
 
+    // An opponent on our own square (only in a malformed state) is never adjacent, so it is ignored
     if (adjacent.row === opponent.row && adjacent.col === opponent.col) {
-      const jump = { row: adjacent.row + delta.row, col: adjacent.col + delta.col };
-
-      if (isWithinBounds(jump) && !isEdgeBlocked(adjacent, jump, blockedEdges)) {
-        moves.push(jump);
-        continue;
-      }
-
-      const perpendicularKey = `${delta.row},${delta.col}`;
-      const diagonals = PERPENDICULARS[perpendicularKey] ?? [];
-
-      for (const diagDelta of diagonals) {
-        const diagonal = { row: adjacent.row + diagDelta.row, col: adjacent.col + diagDelta.col };
-        if (
-          isWithinBounds(diagonal) &&
-          !isEdgeBlocked(adjacent, diagonal, blockedEdges) &&
-          !isEdgeBlocked(current, adjacent, blockedEdges)
-        ) {
-          moves.push(diagonal);
-        }
-      }
+      moves.push(...getJumpLandings(adjacent, delta, blockedEdges));
     } else {
       moves.push(adjacent);
     }
@@ -165,6 +179,21 @@ export function getValidPawnMoves(
   return uniqueMoves;
 }
 
+// `moves` rearranged by `order` for `player`; ties keep getValidPawnMoves' order.
+export function orderPawnMoves(moves: Position[], player: PlayerId, order: PawnMoveOrder): Position[] {
+  const sorted = [...moves];
+  const center = Math.floor(BOARD_SIZE / 2);
+  if (order === 'row-major') {
+    sorted.sort((a, b) => a.row - b.row || a.col - b.col);
+  } else if (order === 'forward-first') {
+    sorted.sort((a, b) => Math.abs(a.row - GOAL_ROW[player]) - Math.abs(b.row - GOAL_ROW[player]));
+  } else if (order === 'center-first') {
+    sorted.sort((a, b) => Math.abs(a.col - center) - Math.abs(b.col - center));
+  }
+  return sorted;
+}
+
+// Whether the walls leave any route to `goalRow`; pawns are ignored, as wall legality requires.
 function bfsHasPath(start: Position, goalRow: number, blockedEdges: Set<string>): boolean {
   const queue: Position[] = [start];
   const visited = new Set<string>([positionKey(start)]);
@@ -191,6 +220,116 @@ function bfsHasPath(start: Position, goalRow: number, blockedEdges: Set<string>)
   return false;
 }
 
+// One shortest route to `goalRow`, excluding `from`; the pawn-free route when only the opponent blocks.
+export function getShortestRoute(
+  from: Position,
+  goalRow: number,
+  blockedEdges: Set<string>,
+  opponent?: Position,
+): Position[] | null {
+  const queue: Position[] = [from];
+  const cameFrom = new Map<string, Position | null>([[positionKey(from), null]]);
+
+  for (let i = 0; i < queue.length; i += 1) {
+    const pos = queue[i];
+    if (pos.row === goalRow) {
+      const route: Position[] = [];
+      let step = pos;
+      let previous = cameFrom.get(positionKey(step));
+      while (previous) {
+        route.unshift(step);
+        step = previous;
+        previous = cameFrom.get(positionKey(step));
+      }
+      return route;
+    }
+
+    for (const move of getValidPawnMoves(pos, opponent || { row: -1, col: -1 }, blockedEdges)) {
+      const key = positionKey(move);
+      if (!cameFrom.has(key)) {
+        cameFrom.set(key, pos);
+        queue.push(move);
+      }
+    }
+  }
+  return opponent ? getShortestRoute(from, goalRow, blockedEdges) : null;
+}
+
+// Pawn moves along getShortestRoute, or 999 when no route exists.
+export function getShortestPathLength(
+  from: Position,
+  goalRow: number,
+  blockedEdges: Set<string>,
+  opponent?: Position,
+): number {
+  const route = getShortestRoute(from, goalRow, blockedEdges, opponent);
+  return route ? route.length : 999;
+}
+
+// Every square reachable from `start` given the walls, in row-major order.
+export function getReachableSquares(start: Position, blockedEdges: Set<string>): Position[] {
+  const queue: Position[] = [start];
+  const visited = new Set<string>([positionKey(start)]);
+
+  for (let i = 0; i < queue.length; i += 1) {
+    const node = queue[i];
+    for (const neighbor of getAdjacentPositions(node)) {
+      if (isEdgeBlocked(node, neighbor, blockedEdges)) {
+        continue;
+      }
+
+      const key = positionKey(neighbor);
+      if (!visited.has(key)) {
+        visited.add(key);
+        queue.push(neighbor);
+      }
+    }
+  }
+
+  return queue.sort((a, b) => a.row - b.row || a.col - b.col);
+}
+
+// Distinct shortest routes from `start` to `goalRow`, pawns ignored, counted up to `limit`.
+export function countShortestRoutes(start: Position, goalRow: number, blockedEdges: Set<string>, limit = 50): number {
+  const queue: Position[] = [start];
+  const distance = new Map<string, number>([[positionKey(start), 0]]);
+  const routes = new Map<string, number>([[positionKey(start), 1]]);
+  let goalDistance = Infinity;
+  let total = 0;
+
+  for (let i = 0; i < queue.length; i += 1) {
+    const node = queue[i];
+    const key = positionKey(node);
+    const dist = distance.get(key)!;
+    if (dist > goalDistance) {
+      break;
+    }
+    if (node.row === goalRow) {
+      goalDistance = dist;
+      total = Math.min(total + routes.get(key)!, limit);
+      continue;
+    }
+
+    for (const neighbor of getAdjacentPositions(node)) {
+      if (isEdgeBlocked(node, neighbor, blockedEdges)) {
+        continue;
+      }
+
+      const neighborKey = positionKey(neighbor);
+      if (!distance.has(neighborKey)) {
+        distance.set(neighborKey, dist + 1);
+        routes.set(neighborKey, 0);
+        queue.push(neighbor);
+      }
+      if (distance.get(neighborKey) === dist + 1) {
+        routes.set(neighborKey, Math.min(routes.get(neighborKey)! + routes.get(key)!, limit));
+      }
+    }
+  }
+
+  return total;
+}
+
 function crossesExistingWall(candidate: Wall, walls: Wall[]): boolean {
   return walls.some(
     (wall) =>
@@ -231,6 +370,44 @@ export function canPlaceWall(
   );
 }
 
+// Why a stored state can't be played from, or null when valid; walls are replayed in placement order.
+export function validateGameState(positions: Record<PlayerId, Position>, walls: Wall[]): string | null {
+  if (positionKey(positions.north) === positionKey(positions.south)) {
+    return 'both pawns are on the same square';
+  }
+
+  const blockedEdges = new Set<string>();
+
+  for (let i = 0; i < walls.length; i += 1) {
+    const wall = walls[i];
+    if (!isWallWithinBounds(wall)) {
+      return `wall ${i + 1} is off the board`;
+    }
+
+    const edges = getWallEdgeKeys(wall);
+    if (crossesExistingWall(wall, walls.slice(0, i)) || edges.some((edge) => blockedEdges.has(edge))) {
+      return `wall ${i + 1} overlaps an earlier wall`;
+    }
+    edges.forEach((edge) => blockedEdges.add(edge));
+  }
+
+  // canPlaceWall never allows sealing a pawn in, so a stored state that does was not reached legally
+  const sealed = getSealedPawns(positions, walls);
+  if (sealed.length > 0) {
+    return `the ${sealed[0]} pawn has no path to its goal`;
+  }
+
+  return null;
+}
+
+// The pawns the walls alone cut off from their goal row; legal play never produces any.
+export function getSealedPawns(positions: Record<PlayerId, Position>, walls: Wall[]): PlayerId[] {
+  const blockedEdges = buildBlockedEdges(walls);
+  return (['north', 'south'] as const).filter(
+    (player) => !bfsHasPath(positions[player], GOAL_ROW[player], blockedEdges),
+  );
+}
+
 export function computeAvailableWalls(
   orientation: Orientation,
   walls: Wall[],
@@ -250,12 +427,386 @@ export function computeAvailableWalls(
   return placements;
 }
 
+// The legal wall whose centre is nearest `target`'s, ties to its orientation; null beyond `maxDistance`.
+export function findNearestLegalWall(
+  target: Wall,
+  walls: Wall[],
+  positions: Record<PlayerId, Position>,
+  maxDistance = 1.5,
+): Wall | null {
+  let nearest: Wall | null = null;
+  let nearestDistance = Infinity;
+
+  const candidates = computeAvailableWalls(target.orientation, walls, positions).concat(
+    computeAvailableWalls(target.orientation === 'horizontal' ? 'vertical' : 'horizontal', walls, positions),
+  );
+  for (const candidate of candidates) {
+    const distance = Math.hypot(candidate.row - target.row, candidate.col - target.col);
+    if (distance < nearestDistance) {
+      nearest = candidate;
+      nearestDistance = distance;
+    }
+  }
+
+  return nearestDistance <= maxDistance ? nearest : null;
+}
+
+// Legal walls for `player` that lengthen the opponent's path, biggest increase first.
+function rankBlockingWalls(
+  player: PlayerId,
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+): { wall: Wall; increase: number }[] {
+  const opponent = getOpponent(player);
+  const pathLength = (edges: Set<string>) =>
+    getShortestPathLength(positions[opponent], GOAL_ROW[opponent], edges, positions[player]);
+  const currentLength = pathLength(buildBlockedEdges(walls));
+
+  const ranked: { wall: Wall; increase: number }[] = [];
+  const candidates = computeAvailableWalls('horizontal', walls, positions).concat(
+    computeAvailableWalls('vertical', walls, positions),
+  );
+  for (const wall of candidates) {
+    const increase = pathLength(buildBlockedEdges([...walls, wall])) - currentLength;
+    if (increase > 0) {
+      ranked.push({ wall, increase });
+    }
+  }
+
+  return ranked.sort((a, b) => b.increase - a.increase);
+}
+
+// The legal wall for `player` that lengthens the opponent's path most, or null when none does.
+export function findBestBlockingWall(
+  player: PlayerId,
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+): { wall: Wall; increase: number } | null {
+  return rankBlockingWalls(player, positions, walls)[0] ?? null;
+}
+
+// Opponent walls adding two or more steps to `side`'s path; empty when they have no walls left.
+export function findOpponentThreats(
+  side: PlayerId,
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+  opponentWallsRemaining: number,
+): { wall: Wall; increase: number }[] {
+  if (opponentWallsRemaining <= 0) {
+    return [];
+  }
+  return rankBlockingWalls(getOpponent(side), positions, walls).filter((threat) => threat.increase >= 2);
+}
+
+// The raw per-side features the AI weighs, uncombined, for charting how a game developed.
+export function getPositionSummary(
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+  wallsRemaining: Record<PlayerId, number>,
+): Record<PlayerId, { distance: number; walls: number; mobility: number }> {
+  const edges = buildBlockedEdges(walls);
+  const summarize = (player: PlayerId) => {
+    const opponent = positions[getOpponent(player)];
+    return {
+      distance: getShortestPathLength(positions[player], GOAL_ROW[player], edges, opponent),
+      walls: wallsRemaining[player],
+      mobility: getValidPawnMoves(positions[player], opponent, edges).length,
+    };
+  };
+  return { north: summarize('north'), south: summarize('south') };
+}
+
 export function isWinningPosition(player: PlayerId, position: Position): boolean {
   return position.row === GOAL_ROW[player];
 }
 
+function compareWalls(a: Wall, b: Wall): number {
+  if (a.orientation !== b.orientation) {
+    return a.orientation < b.orientation ? -1 : 1;
+  }
+  return a.row - b.row || a.col - b.col;
+}
+
+// A sorted copy of `walls`, so the same set compares and hashes equally whatever order it was placed in.
+export function canonicalWalls(walls: Wall[]): Wall[] {
+  return [...walls].sort(compareWalls);
+}
+
+// Pawns and walls as one string, independent of wall order; reserves and side to move excluded.
+export function boardKey(positions: Record<PlayerId, Position>, walls: Wall[]): string {
+  const wallKeys = canonicalWalls(walls).map((wall) => `${wall.orientation[0]}${wall.row},${wall.col}`);
+  return [positionKey(positions.north), positionKey(positions.south), wallKeys.join(' ')].join('|');
+}
+
+export function positionHash(
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+  wallsRemaining: Record<PlayerId, number>,
+  toMove: PlayerId,
+): string {
+  const key = [boardKey(positions, walls), `${wallsRemaining.north},${wallsRemaining.south}`, toMove].join('|');
+
+  // 32-bit FNV-1a
+  let hash = 0x811c9dc5;
+  for (let i = 0; i < key.length; i += 1) {
+    hash ^= key.charCodeAt(i);
+    hash = Math.imul(hash, 0x01000193);
+  }
+  return (hash >>> 0).toString(16).padStart(8, '0');
+}
+
+// Reflects the board left to right. Applying it twice gives back the original square or wall.
+export function mirrorPositionColumns({ row, col }: Position): Position {
+  return { row, col: BOARD_SIZE - 1 - col };
+}
+
+export function mirrorWallColumns(wall: Wall): Wall {
+  return { ...wall, col: BOARD_SIZE - 2 - wall.col };
+}
+
+// One of a position and its mirror image, the same for both; `mirrored` says moves must map back.
+export function canonicalizePosition(
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+): { positions: Record<PlayerId, Position>; walls: Wall[]; mirrored: boolean } {
+  const reflected = {
+    positions: { north: mirrorPositionColumns(positions.north), south: mirrorPositionColumns(positions.south) },
+    walls: walls.map(mirrorWallColumns),
+  };
+  if (boardKey(reflected.positions, reflected.walls) < boardKey(positions, walls)) {
+    return { ...reflected, mirrored: true };
+  }
+  return { positions, walls, mirrored: false };
+}
+
 export function describeWallPlacement(wall: Wall): string {
   const direction = wall.orientation === 'horizontal' ? 'east-west' : 'north-south';
   const article = wall.orientation === 'horizontal' ? 'an' : 'a';
   return `${article} ${direction} wall at row ${wall.row + 1}, column ${wall.col + 1}`;
-}
\ No newline at end of file
+}
+
+export function isGameOver(state: GameState): boolean {
+  return isWinningPosition('north', state.positions.north) || isWinningPosition('south', state.positions.south);
+}
+
+// Pure-race winner with the side to play moving first; 'contested' while the trailer still has walls.
+export function getRaceWinner(state: GameState): PlayerId | 'contested' {
+  const { positions, walls, wallsRemaining, toMove } = state;
+  const other = getOpponent(toMove);
+  if (isGameOver(state)) {
+    return isWinningPosition(toMove, positions[toMove]) ? toMove : other;
+  }
+
+  const edges = buildBlockedEdges(walls);
+  const moverDistance = getShortestPathLength(positions[toMove], GOAL_ROW[toMove], edges, positions[other]);
+  const otherDistance = getShortestPathLength(positions[other], GOAL_ROW[other], edges, positions[toMove]);
+  const winner = moverDistance <= otherDistance ? toMove : other;
+  return wallsRemaining[getOpponent(winner)] > 0 ? 'contested' : winner;
+}
+
+// Every move for the side to play: pawn moves in `pawnMoveOrder`, then walls while it has any.
+export function getLegalMoves(state: GameState, pawnMoveOrder: PawnMoveOrder = 'directional'): Move[] {
+  if (isGameOver(state)) {
+    return [];
+  }
+
+  const { positions, walls, toMove } = state;
+  const pawnMoves = getValidPawnMoves(positions[toMove], positions[getOpponent(toMove)], buildBlockedEdges(walls));
+  const moves: Move[] = orderPawnMoves(pawnMoves, toMove, pawnMoveOrder).map((to): Move => ({ type: 'move', data: to }));
+
+  if (state.wallsRemaining[toMove] > 0) {
+    for (const orientation of ['horizontal', 'vertical'] as const) {
+      for (const wall of computeAvailableWalls(orientation, walls, positions)) {
+        moves.push({ type: 'wall', data: wall });
+      }
+    }
+  }
+
+  return moves;
+}
+
+// The state after the side to play makes `move`, or null when the move is illegal.
+export function applyMove(state: GameState, move: Move): GameState | null {
+  const { positions, walls, wallsRemaining, toMove } = state;
+  if (isGameOver(state)) {
+    return null;
+  }
+
+  if (move.type === 'move') {
+    const { row, col } = move.data;
+    const legal = getValidPawnMoves(positions[toMove], positions[getOpponent(toMove)], buildBlockedEdges(walls));
+    if (!legal.some((to) => to.row === row && to.col === col)) {
+      return null;
+    }
+    return {
+      positions: { ...positions, [toMove]: { row, col } },
+      walls,
+      wallsRemaining,
+      toMove: getOpponent(toMove),
+    };
+  }
+
+  if (wallsRemaining[toMove] <= 0 || !canPlaceWall(move.data, walls, positions)) {
+    return null;
+  }
+  const { row, col, orientation } = move.data;
+  return {
+    positions,
+    walls: [...walls, { row, col, orientation, owner: toMove }],
+    wallsRemaining: { ...wallsRemaining, [toMove]: wallsRemaining[toMove] - 1 },
+    toMove: getOpponent(toMove),
+  };
+}
+
+// Replays `moves` from `initial`: the first illegal move's index, or -1, and the state before it.
+export function validateMoveSequence(
+  initial: GameState,
+  moves: (Move & { player?: PlayerId })[],
+): { firstIllegalMove: number; state: GameState } {
+  let state = initial;
+  for (let i = 0; i < moves.length; i += 1) {
+    const { player } = moves[i];
+    const next = player && player !== state.toMove ? null : applyMove(state, moves[i]);
+    if (!next) {
+      return { firstIllegalMove: i, state };
+    }
+    state = next;
+  }
+
+  return { firstIllegalMove: -1, state };
+}
+
+// Whether `wall` rules out a slot where the opponent could lengthen `player`'s path.
+export function deniesBlockingSlot(
+  player: PlayerId,
+  wall: Wall,
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+): boolean {
+  const opponent = getOpponent(player);
+  const pathLength = (edges: Set<string>) =>
+    getShortestPathLength(positions[player], GOAL_ROW[player], edges, positions[opponent]);
+  const currentLength = pathLength(buildBlockedEdges(walls));
+  const deniedSlots: Wall[] =
+    wall.orientation === 'horizontal'
+      ? [
+          { row: wall.row, col: wall.col, orientation: 'vertical' },
+          { row: wall.row, col: wall.col - 1, orientation: 'horizontal' },
+          { row: wall.row, col: wall.col + 1, orientation: 'horizontal' },
+        ]
+      : [
+          { row: wall.row, col: wall.col, orientation: 'horizontal' },
+          { row: wall.row - 1, col: wall.col, orientation: 'vertical' },
+          { row: wall.row + 1, col: wall.col, orientation: 'vertical' },
+        ];
+
+  return deniedSlots.some(
+    (slot) => canPlaceWall(slot, walls, positions) && pathLength(buildBlockedEdges([...walls, slot])) > currentLength,
+  );
+}
+
+// Phrases used by explainMove, kept together so new cases only need a template here.
+const MOVE_REASONS = {
+  advance: 'Advancing because it shortens your path by {n}',
+  reposition: 'Repositioning without losing ground',
+  retreat: 'Stepping back to get around the walls ahead',
+  noCheapBlock: ' and the opponent cannot block cheaply',
+  block: "Placing a wall to lengthen the opponent's route by {n}",
+  deny: 'Placing a wall to take away a slot the opponent could use against you',
+  placeWall: "Placing a wall that does not lengthen the opponent's route yet",
+  selfCost: ', at a cost of {n} to your own path',
+} as const;
+
+function fillReason(template: string, n: number): string {
+  return template.replace('{n}', String(n));
+}
+
+// A short coaching line for `player` making `move`; `wallsRemaining` says who can still block.
+export function explainMove(
+  player: PlayerId,
+  move: Move,
+  positions: Record<PlayerId, Position>,
+  walls: Wall[],
+  wallsRemaining: Record<PlayerId, number>,
+): string {
+  const opponent = getOpponent(player);
+  const pathLengths = (nextPositions: Record<PlayerId, Position>, nextWalls: Wall[]) => {
+    const edges = buildBlockedEdges(nextWalls);
+    return {
+      own: getShortestPathLength(nextPositions[player], GOAL_ROW[player], edges, nextPositions[opponent]),
+      theirs: getShortestPathLength(nextPositions[opponent], GOAL_ROW[opponent], edges, nextPositions[player]),
+    };
+  };
+  const before = pathLengths(positions, walls);
+
+  if (move.type === 'move') {
+    const nextPositions = { ...positions, [player]: move.data };
+    const gain = before.own - pathLengths(nextPositions, walls).own;
+    if (gain <= 0) {
+      return `${gain === 0 ? MOVE_REASONS.reposition : MOVE_REASONS.retreat}.`;
+    }
+    const counterBlock =
+      wallsRemaining[opponent] > 0 ? (findBestBlockingWall(opponent, nextPositions, walls)?.increase ?? 0) : 0;
+    return `${fillReason(MOVE_REASONS.advance, gain)}${counterBlock <= 1 ? MOVE_REASONS.noCheapBlock : ''}.`;
+  }
+
+  const after = pathLengths(positions, [...walls, move.data]);
+  const increase = after.theirs - before.theirs;
+  const cost = after.own - before.own;
+  let reason: string = MOVE_REASONS.placeWall;
+  if (increase > 0) {
+    reason = fillReason(MOVE_REASONS.block, increase);
+  } else if (wallsRemaining[opponent] > 0 && deniesBlockingSlot(player, move.data, positions, walls)) {
+    reason = MOVE_REASONS.deny;
+  }
+  return `${reason}${cost > 0 ? fillReason(MOVE_REASONS.selfCost, cost) : ''}.`;
+}
+
+// Pawn moves relative to the mover in the fixed order moveToIndex uses: steps, straight jumps, diagonals.
+const PAWN_MOVE_OFFSETS: Position[] = [
+  ...DIRECTIONS,
+  ...DIRECTIONS.map(({ row, col }) => ({ row: row * 2, col: col * 2 })),
+  { row: -1, col: -1 },
+  { row: -1, col: 1 },
+  { row: 1, col: -1 },
+  { row: 1, col: 1 },
+];
+
+const WALL_SLOTS = (BOARD_SIZE - 1) * (BOARD_SIZE - 1);
+
+// Size of the action space moveToIndex maps into, for sizing policy vectors.
+export const MOVE_INDEX_COUNT = PAWN_MOVE_OFFSETS.length + 2 * WALL_SLOTS;
+
+// A stable index for `move` from `from`: pawn offsets, then horizontal and vertical walls; -1 if none.
+export function moveToIndex(from: Position, move: Move): number {
+  if (move.type === 'move') {
+    const { row, col } = move.data;
+    return PAWN_MOVE_OFFSETS.findIndex((offset) => from.row + offset.row === row && from.col + offset.col === col);
+  }
+
+  if (!isWallWithinBounds(move.data)) {
+    return -1;
+  }
+  const { row, col, orientation } = move.data;
+  return PAWN_MOVE_OFFSETS.length + (orientation === 'horizontal' ? 0 : WALL_SLOTS) + row * (BOARD_SIZE - 1) + col;
+}
+
+// The inverse of moveToIndex, or null out of range; legality is not checked.
+export function indexToMove(from: Position, index: number): Move | null {
+  if (!Number.isInteger(index) || index < 0 || index >= MOVE_INDEX_COUNT) {
+    return null;
+  }
+
+  if (index < PAWN_MOVE_OFFSETS.length) {
+    const offset = PAWN_MOVE_OFFSETS[index];
+    return { type: 'move', data: { row: from.row + offset.row, col: from.col + offset.col } };
+  }
+
+  const slot = index - PAWN_MOVE_OFFSETS.length;
+  const orientation: Orientation = slot < WALL_SLOTS ? 'horizontal' : 'vertical';
+  const wallSlot = slot % WALL_SLOTS;
+  return {
+    type: 'wall',
+    data: { row: Math.floor(wallSlot / (BOARD_SIZE - 1)), col: wallSlot % (BOARD_SIZE - 1), orientation },
+  };
+}